@@ -0,0 +1,79 @@
+use crate::{AcpuclkError, Headroom};
+
+/// Tunables baked into the DT output that differ between boards (fuse mask, regulator
+/// timing, PVS bin count, ...). Defaults match the values this crate has always emitted;
+/// pass a parsed [`Config`] in to override them without recompiling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// `opp-supported-hw` bitmask.
+    pub supported_hw: u32,
+    /// `clock-latency-ns` applied to PLL8 rows, to give time to switch PLL8/HFPLL.
+    pub pll8_latency_ns: u32,
+    /// Number of PVS bins a row's `uv` array holds (`opp-microvolt-speedN-pvsM` columns).
+    pub pvs_bins: usize,
+    /// Multiplier applied to a row's kHz frequency to get the `opp-hz` value.
+    pub hz_multiplier: u32,
+    /// Sanity limit on the number of rows a table may produce.
+    pub max_levels: usize,
+    /// Regulator margin applied around each fused voltage to get `<target min max>`.
+    pub headroom: Headroom,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            supported_hw: 0x4007,
+            pll8_latency_ns: 244144,
+            pvs_bins: 7,
+            hz_multiplier: 1000,
+            max_levels: 20,
+            headroom: Headroom::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Parse a `config.txt`-style `key=value`-per-line file, overriding defaults for any
+    /// key present. Blank lines and lines starting with `#` are ignored.
+    pub fn parse(content: &str) -> Result<Self, AcpuclkError> {
+        let mut config = Self::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| AcpuclkError::BadConfigLine { raw: line.to_string() })?;
+            let (key, value) = (key.trim(), value.trim());
+
+            let parse_u32 = |value: &str| -> Result<u32, AcpuclkError> {
+                let parsed = if let Some(hex) = value.strip_prefix("0x") {
+                    u32::from_str_radix(hex, 16)
+                } else {
+                    value.parse()
+                };
+                parsed.map_err(|source| AcpuclkError::InvalidConfigValue { key: key.to_string(), raw: value.to_string(), source })
+            };
+            let parse_usize = |value: &str| -> Result<usize, AcpuclkError> {
+                value
+                    .parse()
+                    .map_err(|source| AcpuclkError::InvalidConfigValue { key: key.to_string(), raw: value.to_string(), source })
+            };
+
+            match key {
+                "supported_hw" => config.supported_hw = parse_u32(value)?,
+                "pll8_latency_ns" => config.pll8_latency_ns = parse_u32(value)?,
+                "pvs_bins" => config.pvs_bins = parse_usize(value)?,
+                "hz_multiplier" => config.hz_multiplier = parse_u32(value)?,
+                "max_levels" => config.max_levels = parse_usize(value)?,
+                "headroom" => config.headroom = Headroom::parse(value)?,
+                _ => return Err(AcpuclkError::UnknownConfigKey { key: key.to_string() }),
+            }
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::num::ParseIntError;
+
+/// Errors produced while parsing an `acpu_level` table or emitting its OPP translation.
+///
+/// Every variant that can be tied back to a specific table row carries the table name
+/// and row index so a malformed downstream kernel can be diagnosed without guessing
+/// which struct broke.
+#[derive(Debug)]
+pub enum AcpuclkError {
+    /// A field was present but failed to parse as the expected integer.
+    InvalidNumber {
+        table: String,
+        row: usize,
+        field: &'static str,
+        raw: String,
+        source: ParseIntError,
+    },
+    /// A row's text didn't match the expected `acpu_level` shape at all.
+    MalformedRow { table: String, row: usize, raw: String },
+    /// The `L2(...)` wrapper didn't contain a usable level value.
+    BadL2Level { table: String, row: usize, raw: String },
+    /// The PVS bin in a `[speed][PVS_FOO] = { table` designator wasn't a number or a known
+    /// `PVS_*` name.
+    BadPvsType { table: String, raw: String },
+    /// A row's PVS bin index fell outside the configured `pvs_bins` width.
+    PvsBinOutOfRange { table: String, row: usize, index: usize, bins: usize },
+    /// Parsed more rows than the `max_levels` sanity limit allows.
+    TooManyLevels { got: usize, limit: usize },
+    /// No `static struct acpu_level ... __initdata` array could be found for a table.
+    NoAcpuTable { table: String },
+    /// A `config.txt` line wasn't `key=value`.
+    BadConfigLine { raw: String },
+    /// A `config.txt` value didn't parse as the expected integer.
+    InvalidConfigValue { key: String, raw: String, source: ParseIntError },
+    /// A `config.txt` key isn't one this crate understands.
+    UnknownConfigKey { key: String },
+    /// A `headroom` value wasn't a number optionally suffixed with `mV`, `uV`, or `%`
+    /// (optionally two such values separated by `/` for an asymmetric margin).
+    InvalidHeadroom { raw: String },
+    Io(std::io::Error),
+}
+
+impl fmt::Display for AcpuclkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber { table, row, field, raw, source } => {
+                write!(f, "table `{table}`, row {row}: field `{field}` (`{raw}`) is not a valid number: {source}")
+            }
+            Self::MalformedRow { table, row, raw } => {
+                write!(f, "table `{table}`, row {row}: row `{raw}` doesn't match the expected acpu_level shape")
+            }
+            Self::BadL2Level { table, row, raw } => {
+                write!(f, "table `{table}`, row {row}: bad L2(...) level `{raw}`, please fix your kernel")
+            }
+            Self::BadPvsType { table, raw } => {
+                write!(f, "table `{table}`: `{raw}` is not a recognized PVS bin")
+            }
+            Self::PvsBinOutOfRange { table, row, index, bins } => {
+                write!(f, "table `{table}`, row {row}: PVS bin index {index} is out of range for pvs_bins={bins}")
+            }
+            Self::TooManyLevels { got, limit } => {
+                write!(f, "parsed {got} rows, which is over the sanity limit of {limit}; if you're sure it's correct output, bump the limit value")
+            }
+            Self::NoAcpuTable { table } => {
+                write!(f, "no acpuclk array found for table `{table}`, please fix your kernel")
+            }
+            Self::BadConfigLine { raw } => {
+                write!(f, "config line `{raw}` is not in `key=value` form")
+            }
+            Self::InvalidConfigValue { key, raw, source } => {
+                write!(f, "config key `{key}` has invalid value `{raw}`: {source}")
+            }
+            Self::UnknownConfigKey { key } => {
+                write!(f, "unknown config key `{key}`")
+            }
+            Self::InvalidHeadroom { raw } => {
+                write!(f, "config key `headroom` has invalid value `{raw}`: expected a number optionally suffixed with `mV`, `uV`, or `%`, e.g. `25mV` or `-25mV/+50mV`")
+            }
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AcpuclkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidNumber { source, .. } => Some(source),
+            Self::InvalidConfigValue { source, .. } => Some(source),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AcpuclkError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
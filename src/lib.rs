@@ -0,0 +1,288 @@
+mod config;
+mod error;
+mod verify;
+mod voltage;
+
+pub use config::Config;
+pub use error::AcpuclkError;
+pub use verify::{verify_against_system, VerifyReport};
+pub use voltage::{Headroom, Margin, VoltageSpec};
+
+use regex::Regex;
+use std::fmt::Write;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+// One row, one match: each named group lines up with a field of `acpu_level`, so a row
+// is captured once instead of being re-scanned token-by-token for every field. `use` is
+// matched but not captured here — USE_REGEX already read it before ROW_REGEX runs.
+static ROW_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        \b(?:0x[0-9A-Fa-f]+|\d+)\b .*?
+        \b(?P<freq>0x[0-9A-Fa-f]+|\d+)\b .*?
+        \b(?P<pll>\w+)\b .*?
+        \b(?P<pll_src>0x[0-9A-Fa-f]+|\w+)\b .*?
+        \b(?P<pll_val>0x[0-9A-Fa-f]+|\w+)\b .*?
+        (?P<l2>\b\w+\([^)]*\)) .*?
+        \b(?P<uv>0x[0-9A-Fa-f]+|\d+)\b
+        ",
+    )
+    .unwrap()
+});
+// Checked before ROW_REGEX: a non-scaling row (`use == 0`) may carry a symbolic freq/PLL
+// (e.g. a `STBY_KHZ` standby entry), which ROW_REGEX's numeric `freq`/`uv` groups would
+// reject outright, so the leading `use` token is matched on its own first.
+static USE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?P<use>\d+)").unwrap());
+static L2_LEVEL_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"L2\((\d+)\)").unwrap());
+
+/// A single parsed `acpu_level` row, translated into the fields an OPP node needs.
+#[derive(Debug)]
+pub struct Row {
+    pub freq: u32,
+    pub is_pll8: bool,
+    pub l2_level: u8,
+    pub perf_level: usize,
+    pub uv: Vec<u32>,
+}
+
+impl Row {
+    /// Render this row as a devicetree `opp-*` node. `Display` prints this text rendered
+    /// with `Config::default()`.
+    pub fn to_opp_node(&self, config: &Config) -> String {
+        let hz = self.freq * config.hz_multiplier;
+
+        let uv = self.uv.iter().enumerate().fold(String::with_capacity(200), |mut s, (i, &uv)| {
+            let spec = VoltageSpec::with_headroom(uv, config.headroom);
+            write!(
+                s,
+                "\topp-microvolt-speed0-pvs{} = <{} {} {}>;{}",
+                i,
+                spec.target_uv,
+                spec.min_uv,
+                spec.max_uv,
+                if i == self.uv.len() - 1 { "" } else { "\n" }
+            )
+            .expect("writing to a String cannot fail");
+
+            s
+        });
+
+        format!(
+            "opp-{} {{
+\topp-hz = /bits/ 64 <{}>;
+{}
+\topp-supported-hw = <0x{:x}>;
+\topp-level = <{}>;{}
+}};
+",
+            hz,
+            hz,
+            uv,
+            config.supported_hw,
+            self.perf_level,
+            if self.is_pll8 {
+                format!(
+                    "\n\t/* give enough time to switch between PLL8 and HFPLL */\n\tclock-latency-ns = <{}>;",
+                    config.pll8_latency_ns
+                )
+            } else {
+                Default::default()
+            }
+        )
+    }
+
+    fn try_parse_and_fixup_level(pvs: u8, dt: &[Row], content: &str, table: &str, row: usize, config: &Config) -> Result<Option<Self>, AcpuclkError> {
+        let invalid = |field: &'static str, raw: &str, source: std::num::ParseIntError| AcpuclkError::InvalidNumber {
+            table: table.to_string(),
+            row,
+            field,
+            raw: raw.to_string(),
+            source,
+        };
+
+        let use_tok = USE_REGEX
+            .captures(content)
+            .and_then(|c| c.name("use"))
+            .ok_or_else(|| AcpuclkError::MalformedRow { table: table.to_string(), row, raw: content.to_string() })?
+            .as_str();
+        let use_for_scaling = use_tok.parse::<u8>().map_err(|e| invalid("use", use_tok, e))? != 0;
+        if !use_for_scaling {
+            return Ok(None);
+        }
+
+        let caps = ROW_REGEX
+            .captures(content)
+            .ok_or_else(|| AcpuclkError::MalformedRow { table: table.to_string(), row, raw: content.to_string() })?;
+
+        let freq_tok = &caps["freq"];
+        let freq = freq_tok.parse().map_err(|e| invalid("freq", freq_tok, e))?;
+        let is_pll8 = &caps["pll"] == "PLL_8";
+        let l2_tok = &caps["l2"];
+        let l2_level = L2_LEVEL_REGEX
+            .captures(l2_tok)
+            .and_then(|c| c.get(1))
+            .ok_or_else(|| AcpuclkError::BadL2Level { table: table.to_string(), row, raw: l2_tok.to_string() })?
+            .as_str()
+            .parse()
+            .map_err(|e| invalid("l2", l2_tok, e))?;
+        let uv_tok = &caps["uv"];
+        let uv_value = uv_tok.parse().map_err(|e| invalid("uv", uv_tok, e))?;
+        let perf_level = if let Some(row) = dt.iter().find(|row| row.l2_level == l2_level) {
+            row.perf_level
+        } else if dt.is_empty() {
+            1
+        } else {
+            dt.iter().last().expect("dt checked non-empty above").perf_level + 1
+        };
+
+        let pvs_idx = usize::from(pvs);
+        if pvs_idx >= config.pvs_bins {
+            return Err(AcpuclkError::PvsBinOutOfRange { table: table.to_string(), row, index: pvs_idx, bins: config.pvs_bins });
+        }
+
+        let mut uv = vec![0; config.pvs_bins];
+        uv[pvs_idx] = uv_value;
+
+        Ok(Some(Self {
+            freq,
+            is_pll8,
+            l2_level,
+            perf_level,
+            uv,
+        }))
+    }
+}
+
+impl Display for Row {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_opp_node(&Config::default()))
+    }
+}
+
+fn pvs_macro_to_index(ty: &str) -> Result<u8, &'static str> {
+    match ty {
+        "PVS_SLOW" => Ok(0),
+        "PVS_NOMINAL" => Ok(2),
+        "PVS_FAST" => Ok(3),
+        "PVS_FASTER" => Ok(4),
+        _ => Err("Bad PVS type"),
+    }
+}
+
+/// Parse a kernel's `acpu_level` C source into the OPP rows it describes.
+///
+/// This is the reusable core of the crate: given the full text of an `acpuclk-*.c` file,
+/// it finds every `static struct acpu_level ... __initdata` table, merges the per-PVS-bin
+/// voltage tables together, and returns one [`Row`] per scaling row of the first table
+/// (rows with the same L2 level share a `perf_level`). Embed this in
+/// a batch DT generator or a test harness without going through the CLI.
+pub fn parse_acpu_table(content: &str, config: &Config) -> Result<Vec<Row>, AcpuclkError> {
+    let pvs_regex = Regex::new(r"\[\s*(.*?)\s*\]\[\s*(.*?)\s*\]\s*=\s*\{\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*").expect("static regex is valid");
+    let array_regex = Regex::new(r"static struct acpu_level .* __initdata = \{([\s\S]*?)\};").expect("static regex is valid");
+    let inner_regex = Regex::new(r"\s*\d+,\s*\{\s*[^}]+\s*\},\s*\w+\(\d+\),\s*\d+").expect("static regex is valid");
+
+    let mut dt = Vec::with_capacity(12);
+
+    let pvs_table = pvs_regex.captures_iter(content).filter_map(|m| {
+        let pvs = m.get(1)?;
+
+        // TODO: always assume speed is a number
+        if pvs.as_str().parse::<u8>().is_ok() {
+            Some((m.get(2)?.as_str(), m.get(3)?.as_str()))
+        } else {
+            None
+        }
+    });
+
+    // acpu_freq_tbl array
+    for (table_match, (pvs_name, table_name)) in array_regex.find_iter(content).map(|m| array_regex.captures(m.as_str())).zip(pvs_table) {
+        let table = table_match.ok_or_else(|| AcpuclkError::NoAcpuTable { table: table_name.to_string() })?;
+        let ty = pvs_name
+            .parse::<u8>()
+            .or_else(|_| pvs_macro_to_index(pvs_name))
+            .map_err(|_| AcpuclkError::BadPvsType { table: table_name.to_string(), raw: pvs_name.to_string() })?;
+        let inner = table
+            .get(1)
+            .ok_or_else(|| AcpuclkError::NoAcpuTable { table: table_name.to_string() })?
+            .as_str();
+
+        // makes sense only if we don't have freqs yet
+        if dt.is_empty() {
+            // for each row in table
+            for (row_idx, row) in inner_regex.find_iter(inner).enumerate() {
+                let row = row.as_str();
+
+                if let Some(row) = Row::try_parse_and_fixup_level(ty, &dt, row, table_name, row_idx, config)? {
+                    dt.push(row);
+                }
+            }
+        } else {
+            // at this point everything is parsed and we just need to update value
+            for (row_idx, row) in inner_regex.find_iter(inner).enumerate() {
+                let row = row.as_str();
+
+                let use_tok = USE_REGEX
+                    .captures(row)
+                    .and_then(|c| c.name("use"))
+                    .ok_or_else(|| AcpuclkError::MalformedRow { table: table_name.to_string(), row: row_idx, raw: row.to_string() })?
+                    .as_str();
+                let use_for_scaling = use_tok
+                    .parse::<u8>()
+                    .map_err(|e| AcpuclkError::InvalidNumber { table: table_name.to_string(), row: row_idx, field: "use", raw: use_tok.to_string(), source: e })?
+                    != 0;
+                if !use_for_scaling {
+                    continue;
+                }
+
+                let caps = ROW_REGEX
+                    .captures(row)
+                    .ok_or_else(|| AcpuclkError::MalformedRow { table: table_name.to_string(), row: row_idx, raw: row.to_string() })?;
+
+                let freq_tok = &caps["freq"];
+                let freq = freq_tok
+                    .parse()
+                    .map_err(|e| AcpuclkError::InvalidNumber { table: table_name.to_string(), row: row_idx, field: "freq", raw: freq_tok.to_string(), source: e })?;
+                if let Some(item) = dt.iter_mut().find(|row| row.freq == freq) {
+                    let ty_idx = usize::from(ty);
+                    if ty_idx >= item.uv.len() {
+                        return Err(AcpuclkError::PvsBinOutOfRange { table: table_name.to_string(), row: row_idx, index: ty_idx, bins: config.pvs_bins });
+                    }
+
+                    let uv_tok = &caps["uv"];
+                    item.uv[ty_idx] = uv_tok
+                        .parse()
+                        .map_err(|e| AcpuclkError::InvalidNumber { table: table_name.to_string(), row: row_idx, field: "uv", raw: uv_tok.to_string(), source: e })?;
+                }
+            }
+        }
+    }
+
+    if dt.len() > config.max_levels {
+        Err(AcpuclkError::TooManyLevels { got: dt.len(), limit: config.max_levels })
+    } else {
+        Ok(dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE_WITH_STANDBY_ROW: &str = r#"
+static struct acpu_level table_a[] __initdata = {
+    { 0, { STBY_KHZ, QSB, 0, 0 }, L2(0), 0 },
+    { 1, { 384000, PLL_8, 0, 2 }, L2(1), 950000 },
+};
+
+[0][2] = { table_a,
+"#;
+
+    #[test]
+    fn skips_non_scaling_rows_with_symbolic_freq() {
+        let rows = parse_acpu_table(TABLE_WITH_STANDBY_ROW, &Config::default()).expect("standby row should be skipped, not rejected");
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].freq, 384000);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{AcpuclkError, Config, Row};
+use std::collections::BTreeSet;
+use std::fs;
+
+const CPUFREQ_AVAILABLE_FREQS: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_available_frequencies";
+const DEBUGFS_OPP_DIR: &str = "/sys/kernel/debug/opp";
+
+/// Difference between a generated OPP table and what the running kernel actually exposes.
+#[derive(Debug, Default, PartialEq)]
+pub struct VerifyReport {
+    /// `opp-hz` values (Hz) present in the generated table but missing on-device.
+    pub missing_on_device: Vec<u64>,
+    /// Frequencies (Hz) the running kernel reports that aren't in the generated table.
+    pub extra_on_device: Vec<u64>,
+}
+
+/// Compare a generated OPP table against what the running board is really exposing.
+///
+/// Reads `scaling_available_frequencies` from cpufreq and any `rate` files under the OPP
+/// debugfs tree (the way `systemstat` scrapes Linux `/sys`), then diffs the union against
+/// `rows`' `opp-hz` values. Lets a maintainer catch PLL8-vs-HFPLL mismatches the static
+/// parse can't see before committing a device tree.
+pub fn verify_against_system(rows: &[Row], config: &Config) -> Result<VerifyReport, AcpuclkError> {
+    let generated: BTreeSet<u64> = rows.iter().map(|row| u64::from(row.freq) * u64::from(config.hz_multiplier)).collect();
+
+    let mut on_device: BTreeSet<u64> = read_cpufreq_available_frequencies()?.into_iter().collect();
+    on_device.extend(read_debugfs_opp_rates());
+
+    Ok(VerifyReport {
+        missing_on_device: generated.difference(&on_device).copied().collect(),
+        extra_on_device: on_device.difference(&generated).copied().collect(),
+    })
+}
+
+fn read_cpufreq_available_frequencies() -> Result<Vec<u64>, AcpuclkError> {
+    let content = fs::read_to_string(CPUFREQ_AVAILABLE_FREQS)?;
+
+    Ok(content.split_whitespace().filter_map(|khz| khz.parse::<u64>().ok()).map(|khz| khz * 1000).collect())
+}
+
+// OPP debugfs is a best-effort source: not every kernel mounts debugfs or exposes this
+// tree, so a missing/unreadable entry here is silently skipped rather than failing verify.
+fn read_debugfs_opp_rates() -> Vec<u64> {
+    let Ok(opp_tables) = fs::read_dir(DEBUGFS_OPP_DIR) else {
+        return Vec::new();
+    };
+
+    opp_tables
+        .flatten()
+        .filter_map(|table| fs::read_dir(table.path()).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|opp| fs::read_to_string(opp.path().join("rate")).ok())
+        .filter_map(|rate| rate.trim().parse().ok())
+        .collect()
+}
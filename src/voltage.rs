@@ -0,0 +1,157 @@
+use crate::AcpuclkError;
+
+/// One side of a margin: an absolute microvolt delta, or a percentage of the target.
+///
+/// Parsed the way coreutils `dd` parses human-friendly size arguments: a bare or
+/// `mV`/`uV`-suffixed number is an absolute margin in microvolts, a `%`-suffixed number
+/// is relative to the target voltage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Margin {
+    Microvolts(u32),
+    Percent(f64),
+}
+
+impl Margin {
+    fn parse(raw: &str) -> Option<Self> {
+        let trimmed = raw.trim();
+
+        if let Some(pct) = trimmed.strip_suffix('%') {
+            return pct.trim().parse::<f64>().ok().map(Self::Percent);
+        }
+
+        let uv = trimmed
+            .strip_suffix("mV")
+            .or_else(|| trimmed.strip_suffix("mv"))
+            .map(|mv| mv.trim().parse::<u32>().ok().map(|mv| mv * 1000))
+            .or_else(|| {
+                trimmed
+                    .strip_suffix("uV")
+                    .or_else(|| trimmed.strip_suffix("uv"))
+                    .or_else(|| trimmed.strip_suffix("\u{b5}V"))
+                    .map(|uv| uv.trim().parse::<u32>().ok())
+            })
+            .unwrap_or_else(|| trimmed.parse::<u32>().ok())?;
+
+        Some(Self::Microvolts(uv))
+    }
+
+    fn delta(self, target_uv: u32) -> u32 {
+        match self {
+            Self::Microvolts(delta) => delta,
+            Self::Percent(pct) => (f64::from(target_uv) * pct / 100.0).round() as u32,
+        }
+    }
+}
+
+/// Regulator margin applied around a fused voltage to get the DT `<target min max>` triplet.
+///
+/// A single spec (`25mV`, `3%`) applies the same margin below and above the target. A
+/// `down/up` pair (`-25mV/+50mV`, `2%/5%`) applies a different margin each side, for
+/// regulators whose scaling headroom isn't symmetric.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Headroom {
+    Symmetric(Margin),
+    Asymmetric { down: Margin, up: Margin },
+}
+
+impl Default for Headroom {
+    /// No margin: min and max collapse onto the target, matching this crate's old output.
+    fn default() -> Self {
+        Self::Symmetric(Margin::Microvolts(0))
+    }
+}
+
+impl Headroom {
+    pub fn parse(raw: &str) -> Result<Self, AcpuclkError> {
+        let bad = || AcpuclkError::InvalidHeadroom { raw: raw.to_string() };
+        let trimmed = raw.trim();
+
+        if let Some((down, up)) = trimmed.split_once('/') {
+            let down = Margin::parse(down.trim().trim_start_matches('-')).ok_or_else(bad)?;
+            let up = Margin::parse(up.trim().trim_start_matches('+')).ok_or_else(bad)?;
+            return Ok(Self::Asymmetric { down, up });
+        }
+
+        Margin::parse(trimmed).map(Self::Symmetric).ok_or_else(bad)
+    }
+
+    fn bounds(self, target_uv: u32) -> (u32, u32) {
+        let (down, up) = match self {
+            Self::Symmetric(margin) => (margin, margin),
+            Self::Asymmetric { down, up } => (down, up),
+        };
+
+        (target_uv.saturating_sub(down.delta(target_uv)), target_uv.saturating_add(up.delta(target_uv)))
+    }
+}
+
+/// A regulator voltage triplet in the devicetree `<target min max>` convention.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoltageSpec {
+    pub target_uv: u32,
+    pub min_uv: u32,
+    pub max_uv: u32,
+}
+
+impl VoltageSpec {
+    /// An unpopulated PVS bin (`target_uv == 0`) has no real voltage to apply headroom
+    /// around, so it stays `<0 0 0>` instead of picking up a nonsensical margin.
+    pub fn with_headroom(target_uv: u32, headroom: Headroom) -> Self {
+        let (min_uv, max_uv) = if target_uv == 0 { (0, 0) } else { headroom.bounds(target_uv) };
+        Self { target_uv, min_uv, max_uv }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpopulated_bin_stays_zero_regardless_of_headroom() {
+        let spec = VoltageSpec::with_headroom(0, Headroom::parse("25mV").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 0, min_uv: 0, max_uv: 0 });
+    }
+
+    #[test]
+    fn symmetric_microvolt_headroom_applies_equally_both_sides() {
+        let spec = VoltageSpec::with_headroom(950_000, Headroom::parse("25mV").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 950_000, min_uv: 925_000, max_uv: 975_000 });
+    }
+
+    #[test]
+    fn symmetric_percent_headroom_rounds_to_nearest_microvolt() {
+        let spec = VoltageSpec::with_headroom(950_000, Headroom::parse("3%").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 950_000, min_uv: 921_500, max_uv: 978_500 });
+    }
+
+    #[test]
+    fn asymmetric_headroom_applies_a_different_margin_each_side() {
+        let spec = VoltageSpec::with_headroom(950_000, Headroom::parse("-25mV/+50mV").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 950_000, min_uv: 925_000, max_uv: 1_000_000 });
+    }
+
+    #[test]
+    fn asymmetric_percent_headroom() {
+        let spec = VoltageSpec::with_headroom(1_000_000, Headroom::parse("2%/5%").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 1_000_000, min_uv: 980_000, max_uv: 1_050_000 });
+    }
+
+    #[test]
+    fn microvolt_suffix_is_accepted() {
+        let spec = VoltageSpec::with_headroom(950_000, Headroom::parse("5000uV").unwrap());
+
+        assert_eq!(spec, VoltageSpec { target_uv: 950_000, min_uv: 945_000, max_uv: 955_000 });
+    }
+
+    #[test]
+    fn headroom_saturates_instead_of_underflowing() {
+        let spec = VoltageSpec::with_headroom(10_000, Headroom::parse("25mV").unwrap());
+
+        assert_eq!(spec.min_uv, 0);
+    }
+}